@@ -1,17 +1,40 @@
+use crate::configuration::unwatched::UnwatchedDependency;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::error::PyreError;
+use crate::fingerprint::FingerprintStore;
+use crate::log_file::LogFile;
 use serde::{Deserialize, Serialize};
-use std::convert::From;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
 use std::process::{Command, Stdio};
 use std::string::String;
+use std::time::Instant;
 
-#[derive(Debug)]
-pub struct CheckError {
-    pub msg: &'static str,
-}
+/// Rotate profiling logs once they pass 10 MiB, keeping 4 prior rotations.
+const PROFILING_LOG_MAX_SIZE: u64 = 10 * 1024 * 1024;
+const PROFILING_LOG_MAX_FILES: u32 = 4;
+
+/// Passing this as the argument-file path (or setting `--args-from-stdin`)
+/// tells `check_command` to read the check argument JSON payload from stdin
+/// instead of a file on disk.
+const ARGUMENT_FILE_STDIN_MARKER: &str = "-";
 
-impl From<std::io::Error> for CheckError {
-    fn from(_e: std::io::Error) -> Self {
-        CheckError { msg: "I/O error" }
+/// If `argument_file_path` requests stdin, read the payload and materialize it
+/// as a temp file so it can still be passed to the `newcheck` subprocess by
+/// path; otherwise pass the path through unchanged.
+fn resolve_argument_file_path(argument_file_path: &str) -> Result<String, PyreError> {
+    if argument_file_path != ARGUMENT_FILE_STDIN_MARKER {
+        return Ok(argument_file_path.to_string());
     }
+    let mut payload = String::new();
+    std::io::stdin().read_to_string(&mut payload)?;
+    let temp_path = std::env::temp_dir().join(format!("pyre_check_args_{}.json", std::process::id()));
+    fs::write(&temp_path, payload)?;
+    Ok(temp_path
+        .into_os_string()
+        .into_string()
+        .expect("Invalid UTF-8"))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -34,33 +57,190 @@ fn parse_type_error_response(val: &str) -> Vec<TypeError> {
     type_errors
 }
 
-fn display_type_errors(errors: &Vec<TypeError>) {
-    for e in errors {
-        println!("{:#?}", e);
+/// Rewrite `path` for display: relative to `cwd` when the file is under it
+/// (the common case, and the most copy-pasteable from a terminal), otherwise
+/// relative to `configuration_root`, otherwise the original absolute path
+/// unchanged. This makes output stable regardless of which directory `pyre`
+/// was invoked from.
+fn relativize_path(path: &str, configuration_root: &str, cwd: &str) -> String {
+    let absolute = Path::new(path);
+    if let Ok(relative_to_cwd) = absolute.strip_prefix(cwd) {
+        return relative_to_cwd.to_string_lossy().into_owned();
+    }
+    if let Ok(relative_to_root) = absolute.strip_prefix(configuration_root) {
+        return relative_to_root.to_string_lossy().into_owned();
+    }
+    path.to_string()
+}
+
+/// Apply [`relativize_path`] to every error's `path` in place.
+fn relativize_type_error_paths(errors: &mut Vec<TypeError>, configuration_root: &str, cwd: &str) {
+    for error in errors {
+        error.path = relativize_path(&error.path, configuration_root, cwd);
+    }
+}
+
+impl TypeError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            path: self.path.clone(),
+            line: self.line,
+            column: self.column,
+            stop_line: self.stop_line,
+            stop_column: self.stop_column,
+            // The backend only reports hard type errors today; surface them all
+            // as errors until a warning channel exists.
+            severity: Severity::Error,
+            code: self.code.to_string(),
+            description: self.description.clone(),
+            concise_description: if self.concise_description.is_empty() {
+                None
+            } else {
+                Some(self.concise_description.clone())
+            },
+        }
     }
 }
 
-pub fn check_command(binary_location: &str, argument_file_path: &str) -> Result<(), CheckError> {
+/// The `--output` formats `check_command` can render type errors as.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum OutputFormat {
+    /// One compact line per error: `path:line:column code: description`.
+    Text,
+    /// A single JSON array of [`Diagnostic`]s.
+    Json,
+    /// Like `Text`, but stdout is flushed explicitly after each error line.
+    /// The errors are still parsed from `newcheck`'s complete response
+    /// first: the backend returns a single JSON array, so nothing is printed
+    /// before it has fully responded.
+    Streaming,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self, PyreError> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "streaming" => Ok(OutputFormat::Streaming),
+            other => Err(PyreError::Check(format!(
+                "Unrecognized output format `{}`; expected one of: text, json, streaming",
+                other
+            ))),
+        }
+    }
+}
+
+/// Render a single error as `path:line:column code: description`, preferring
+/// the concise description and falling back to the full one when empty.
+fn render_compact_line(error: &TypeError) -> String {
+    let description = if error.concise_description.is_empty() {
+        &error.description
+    } else {
+        &error.concise_description
+    };
+    format!(
+        "{}:{}:{} {}: {}",
+        error.path, error.line, error.column, error.code, description
+    )
+}
+
+fn display_type_errors_text(errors: &Vec<TypeError>) {
+    for error in errors {
+        println!("{}", render_compact_line(error));
+    }
+}
+
+fn display_type_errors_streaming(errors: &Vec<TypeError>) {
+    let mut stdout = io::stdout();
+    for error in errors {
+        println!("{}", render_compact_line(error));
+        let _ = stdout.flush();
+    }
+}
+
+fn display_type_errors_json(errors: &Vec<TypeError>) {
+    let diagnostics = errors
+        .iter()
+        .map(TypeError::to_diagnostic)
+        .collect::<Vec<_>>();
+    let rendered = serde_json::to_string(&diagnostics).expect("Cannot serialize diagnostics");
+    println!("{}", rendered);
+}
+
+fn profiling_log(log_directory: &str, name: &str) -> LogFile {
+    LogFile::new(log_directory, name)
+        .max_size(Some(PROFILING_LOG_MAX_SIZE))
+        .max_files(PROFILING_LOG_MAX_FILES)
+}
+
+/// Name of the on-disk [`FingerprintStore`] file kept alongside the other
+/// per-project state under `log_directory` (the `.pyre` directory).
+const FINGERPRINT_STORE_NAME: &str = "fingerprints.json";
+
+pub fn check_command(
+    binary_location: &str,
+    argument_file_path: &str,
+    output: &str,
+    log_directory: &str,
+    enable_profiling: bool,
+    enable_memory_profiling: bool,
+    configuration_root: &str,
+    cwd: &str,
+    unwatched_dependency: Option<&UnwatchedDependency>,
+    force: bool,
+) -> Result<(), PyreError> {
+    let output_format = OutputFormat::parse(output)?;
+    let fingerprint_store_path = Path::new(log_directory)
+        .join(FINGERPRINT_STORE_NAME)
+        .into_os_string()
+        .into_string()
+        .expect("Invalid UTF-8");
+    let mut fingerprint_store = FingerprintStore::load(&fingerprint_store_path);
+    if let Some(dependency) = unwatched_dependency {
+        if fingerprint_store.dirty_paths(dependency, force).is_empty() {
+            println!("No unwatched dependency files changed since the last check; skipping.");
+            return Ok(());
+        }
+    }
+    let argument_file_path = resolve_argument_file_path(argument_file_path)?;
+    let started_at = Instant::now();
     let child = Command::new(binary_location)
         .arg("newcheck")
-        .arg(argument_file_path)
+        .arg(&argument_file_path)
         .stdout(Stdio::piped())
         .spawn()?;
-    let output = child.wait_with_output()?;
-    if !output.status.success() {
-        return Err(CheckError {
-            msg: "Command failed",
-        });
+    let process_output = child.wait_with_output()?;
+    if enable_profiling {
+        profiling_log(log_directory, "profiling").append(
+            format!("newcheck took {:?}\n", started_at.elapsed()).as_bytes(),
+        )?;
+    }
+    if enable_memory_profiling {
+        profiling_log(log_directory, "memory_profiling").append(
+            format!(
+                "newcheck produced {} bytes of stdout\n",
+                process_output.stdout.len()
+            )
+            .as_bytes(),
+        )?;
+    }
+    if !process_output.status.success() {
+        return Err(PyreError::Check("Command failed".to_string()));
+    }
+    if unwatched_dependency.is_some() {
+        fingerprint_store.save(&fingerprint_store_path);
+    }
+    let stdout = String::from_utf8(process_output.stdout).expect("Cannot decode as utf-8");
+    let mut type_errors = parse_type_error_response(&stdout);
+    relativize_type_error_paths(&mut type_errors, configuration_root, cwd);
+    match output_format {
+        OutputFormat::Json => display_type_errors_json(&type_errors),
+        OutputFormat::Text => display_type_errors_text(&type_errors),
+        OutputFormat::Streaming => display_type_errors_streaming(&type_errors),
     }
-    println!("{:#?}", output.status);
-    let stdout = String::from_utf8(output.stdout).expect("Cannot decode as utf-8");
-    let type_errors = parse_type_error_response(&stdout);
-    display_type_errors(&type_errors);
     if type_errors.len() == 0 {
         Ok(())
     } else {
-        Err(CheckError {
-            msg: "Had some type errors",
-        })
+        Err(PyreError::Check("Had some type errors".to_string()))
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::PyreError;
 use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser, Debug)]
@@ -79,6 +80,8 @@ pub struct CommandArguments {
     #[clap(long)]
     pub enable_find_symbols: Option<bool>,
     #[clap(long)]
+    pub enable_find_all_references: Option<bool>,
+    #[clap(long)]
     pub use_buck2: Option<bool>,
 
     #[clap(subcommand)]
@@ -88,6 +91,12 @@ pub struct CommandArguments {
 #[derive(Args, Debug)]
 pub struct AnalysisArgs;
 
+#[derive(Args, Debug)]
+pub struct LspArgs;
+
+#[derive(Args, Debug)]
+pub struct DumpConfigArgs;
+
 #[derive(Args, Debug)]
 pub struct CheckArgs {
     /// Set debug mode
@@ -101,12 +110,20 @@ pub struct CheckArgs {
     logging_sections: Option<String>,
     #[clap(long)]
     noninteractive: bool,
+    /// Overrides the top-level `--output` format for this subcommand only.
     #[clap(long)]
-    output: Option<String>,
+    pub output: Option<String>,
     #[clap(long)]
     sequential: bool,
     #[clap(long)]
     show_error_traces: bool,
+    /// Ignore the cached fingerprint store and recheck every unwatched file.
+    #[clap(long)]
+    pub force: bool,
+    /// Read the check argument JSON payload from stdin instead of a file on
+    /// disk. Equivalent to passing `-` as the argument file path.
+    #[clap(long)]
+    pub args_from_stdin: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -114,8 +131,15 @@ pub enum Commands {
     Analysis(AnalysisArgs),
     /// Runs check stuff
     Check(CheckArgs),
+    /// Runs the Language Server Protocol loop over stdio
+    Lsp(LspArgs),
+    /// Prints each effective configuration setting and the layer that supplied it
+    DumpConfig(DumpConfigArgs),
 }
 
-pub fn get_args() -> CommandArguments {
-    CommandArguments::parse()
+/// Parse CLI arguments, returning a [`PyreError`] on a malformed invocation
+/// instead of printing usage and exiting the process, so the whole flow
+/// (including argument parsing) can be exercised from tests.
+pub fn get_args() -> Result<CommandArguments, PyreError> {
+    CommandArguments::try_parse().map_err(|e| PyreError::Configuration(e.to_string()))
 }
@@ -7,6 +7,9 @@ use crate::configuration::search_path::SimpleRawElement;
 use crate::configuration::shared_memory::SharedMemory;
 use crate::configuration::site_packages::SearchStrategy;
 use crate::configuration::unwatched::UnwatchedDependency;
+use crate::context::{path_to_string, Context};
+use crate::error::{format_error_chain, PyreError};
+use crate::filesystem::expand_relative_path;
 use crate::find_directories::{
     find_global_and_local_root, get_relative_local_root, CONFIGURATION_FILE,
     LOCAL_CONFIGURATION_FILE, LOG_DIRECTORY,
@@ -61,6 +64,9 @@ impl Configuration {
         partial_configuration: PartialConfiguration,
     ) -> Self {
         let search_path = partial_configuration.search_path;
+        // Apply the recommended profile first so that any field the user set by
+        // hand still overrides it below.
+        let profile = partial_configuration.tweak_defaults.unwrap_or(false);
 
         return Self {
             project_root: String::from(project_root),
@@ -72,7 +78,29 @@ impl Configuration {
             do_not_ignore_errors_in: partial_configuration.do_not_ignore_errors_in,
             excludes: partial_configuration.excludes,
             extensions: partial_configuration.extensions,
-            ide_features: partial_configuration.ide_features,
+            // Merge per-toggle rather than falling back to the profile only
+            // when the user set none of `ide_features` at all -- otherwise
+            // setting a single toggle by hand loses the profile's other
+            // three recommended defaults instead of just overriding the one
+            // the user actually touched.
+            ide_features: if profile {
+                Some(IdeFeatures::merge(
+                    IdeFeatures {
+                        hover_enabled: Some(true),
+                        go_to_definition_enabled: Some(true),
+                        find_symbols_enabled: Some(true),
+                        find_all_references_enabled: Some(true),
+                    },
+                    partial_configuration.ide_features.unwrap_or(IdeFeatures {
+                        hover_enabled: None,
+                        go_to_definition_enabled: None,
+                        find_symbols_enabled: None,
+                        find_all_references_enabled: None,
+                    }),
+                ))
+            } else {
+                partial_configuration.ide_features
+            },
             ignore_all_errors: partial_configuration.ignore_all_errors,
             isolation_prefix: partial_configuration.isolation_prefix,
             logger: partial_configuration.logger,
@@ -89,10 +117,14 @@ impl Configuration {
             shared_memory: partial_configuration.shared_memory,
             site_package_search_strategy: partial_configuration
                 .site_package_search_strategy
-                .unwrap_or(SearchStrategy::NONE),
+                .unwrap_or(if profile {
+                    SearchStrategy::PEP561
+                } else {
+                    SearchStrategy::NONE
+                }),
             site_roots: partial_configuration.site_roots,
             source_directories: partial_configuration.source_directories,
-            strict: partial_configuration.strict.unwrap_or(false),
+            strict: partial_configuration.strict.unwrap_or(profile),
             taint_models_path: partial_configuration.taint_models_path,
             targets: partial_configuration.targets,
             typeshed: partial_configuration.typeshed,
@@ -107,6 +139,23 @@ impl Configuration {
 struct PartialConfiguration {
     binary: Option<String>,
     buck_mode: Option<String>,
+    /// Other configuration files this one composes on top of. Resolved relative
+    /// to the including file's directory and merged *before* this file's own
+    /// keys, so the including file wins on conflicts.
+    #[serde(default)]
+    extends: Vec<String>,
+    /// Names of accumulating list fields this layer asks to reset (via its
+    /// `"reset": [...]` key) so inherited entries are
+    /// cleared from the base before this layer's own values are applied. Kept as
+    /// separate state rather than an empty `Vec` so "not mentioned" stays
+    /// distinguishable from "explicitly reset".
+    #[serde(default, skip_serializing)]
+    reset: HashSet<String>,
+    /// Opt into a curated bundle of recommended defaults (strict mode, all IDE
+    /// features, and the PEP 561 site-package search strategy) with a single
+    /// key. Explicit user values still win, since the profile is applied before
+    /// they are layered in [`Configuration::from_partial_configuration`].
+    tweak_defaults: Option<bool>,
     do_not_ignore_errors_in: Vec<String>,
     dot_pyre_directory: Option<String>,
     excludes: Vec<String>,
@@ -182,6 +231,9 @@ impl PartialConfiguration {
         PartialConfiguration {
             binary: arguments.binary,
             buck_mode: Some(from_json(arguments.buck_mode, "buck_mode")),
+            extends: vec![],
+            reset: HashSet::new(),
+            tweak_defaults: None,
             do_not_ignore_errors_in: arguments.do_not_ignore_errors_in,
             dot_pyre_directory: arguments.dot_pyre_directory,
             excludes: arguments.exclude,
@@ -314,8 +366,27 @@ impl PartialConfiguration {
 
         let configuration_json: PartialConfiguration = serde_json::from_str(contents);
 
+        // Parse strictly so a typo'd key under `unwatched_dependency` (e.g.
+        // `unwatched_dependency.files.extra_key`) surfaces as a warning instead
+        // of being silently dropped by a lenient deserialize.
+        let unwatched_dependency = match configuration_json.pop("unwatched_dependency", None) {
+            Some(value) => match UnwatchedDependency::from_json_strict(&value.to_string()) {
+                Ok(dependency) => Some(dependency),
+                Err(error) => {
+                    println!("{}", format_error_chain(&error));
+                    None
+                }
+            },
+            None => None,
+        };
+
         let partial_configuration = PartialConfiguration {
             binary: ensure_option_type(configuration_json, "binary", str),
+            extends: ensure_string_list(configuration_json, "extends"),
+            reset: ensure_string_list(configuration_json, "reset")
+                .into_iter()
+                .collect(),
+            tweak_defaults: ensure_option_type(configuration_json, "tweak_defaults", bool),
             buck_mode: from_json(
                 ensure_optional_string_or_string_dict(configuration_json, "buck_mode"),
                 "buck_mode",
@@ -357,30 +428,113 @@ impl PartialConfiguration {
             version_hash: ensure_option_type(configuration_json, "version", str),
         };
 
-        // Check for deprecated and unused keys
-        for (deprecated_key, replacement_key) in PartialConfiguration::_get_depreacted_map().items()
-        {
-            if configuration_json.contains(deprecated_key) {
-                configuration_json.pop(deprecated_key);
-                // warning
-                println!(
-                    "Configuration file uses deprecated item `{}`. Please migrate to its replacement `{}`", deprecated_key, replacement_key
-                )
-            }
-        }
+        // Check for deprecated, unrecognized, and typo'd keys in a single pass:
+        // deprecated keys get a migration hint, everything else gets a typo
+        // suggestion (or a plain "unrecognized" message) when nothing fits.
         let extra_keys = PartialConfiguration::_get_extra_keys();
+        let deprecated_map = PartialConfiguration::_get_depreacted_map();
         for unrecognized_key in configuration_json {
-            if !extra_keys.contains(unrecognized_key) {
-                // warning
-                println!("Unrecognized configuration item: {}", unrecognized_key)
+            if extra_keys.contains(unrecognized_key) {
+                continue;
+            }
+            // A deprecated key has a known migration, so prefer reporting that
+            // over a fuzzy typo suggestion.
+            if let Some(replacement_key) = deprecated_map.get(unrecognized_key) {
+                println!(
+                    "Configuration file uses deprecated item `{}`. Please migrate to its replacement `{}`",
+                    unrecognized_key, replacement_key
+                );
+                continue;
+            }
+            match closest_known_field(unrecognized_key) {
+                Some(suggestion) => println!(
+                    "Unrecognized configuration item: {}. Did you mean `{}`?",
+                    unrecognized_key, suggestion
+                ),
+                None => println!("Unrecognized configuration item: {}", unrecognized_key),
             }
         }
 
         partial_configuration
     }
 
-    fn from_file(path: &str) -> Self {
-        Self::from_string(fs::open(path).read_text()?)
+    fn from_file(path: &str) -> Result<Self, PyreError> {
+        let mut visited = HashSet::new();
+        Self::from_file_with_visited(path, &mut visited)
+    }
+
+    /// Load `path`, then recursively load and merge every file named in its
+    /// `extends` list. Included files are merged first (as the `base`) so the
+    /// including file's own keys overwrite them. `visited` carries the set of
+    /// already-loaded paths down the recursion to detect and break include
+    /// cycles, returning a [`PyreError::Configuration`] instead of aborting the
+    /// process when one is found.
+    fn from_file_with_visited(
+        path: &str,
+        visited: &mut HashSet<String>,
+    ) -> Result<Self, PyreError> {
+        if !visited.insert(path.to_string()) {
+            return Err(PyreError::Configuration(format!(
+                "Cyclic configuration include detected at `{}`",
+                path
+            )));
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let partial = Self::from_string(&contents);
+        let directory = Path::new(path)
+            .parent()
+            .and_then(Path::to_str)
+            .unwrap_or(".");
+        let mut merged = PartialConfiguration::default();
+        for included in &partial.extends {
+            let included_path = expand_relative_path(directory, included)?;
+            let included_partial =
+                Self::from_file_with_visited(&included_path, visited)?.expand_relative_paths(directory);
+            merged = merge_partial_configurations(merged, included_partial);
+        }
+        visited.remove(path);
+        Ok(merge_partial_configurations(merged, partial))
+    }
+
+    /// Like [`from_file_with_visited`], but instead of flattening `extends`
+    /// into a single merged result, return one [`ConfigLayer`] per file so
+    /// `dump_config` can attribute a setting to the exact file that supplied
+    /// it. `label` identifies `path` itself; each included file is labeled
+    /// `"<label> -> <included path>"`.
+    fn layers_for_file(
+        path: &str,
+        label: &str,
+        visited: &mut HashSet<String>,
+    ) -> Result<Vec<ConfigLayer>, PyreError> {
+        if !visited.insert(path.to_string()) {
+            return Err(PyreError::Configuration(format!(
+                "Cyclic configuration include detected at `{}`",
+                path
+            )));
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let partial = Self::from_string(&contents);
+        let directory = Path::new(path)
+            .parent()
+            .and_then(Path::to_str)
+            .unwrap_or(".");
+        let mut layers = Vec::new();
+        for included in &partial.extends {
+            let included_path = expand_relative_path(directory, included)?;
+            let included_label = format!("{} -> {}", label, included_path);
+            layers.extend(Self::layers_for_file(
+                &included_path,
+                &included_label,
+                visited,
+            )?);
+        }
+        layers.push(ConfigLayer {
+            label: label.to_string(),
+            path: path.to_string(),
+            partial: partial.expand_relative_paths(directory),
+        });
+        visited.remove(path);
+        Ok(layers)
     }
 
     fn expand_relative_paths(&self, root: &str) -> Self {
@@ -415,6 +569,9 @@ impl PartialConfiguration {
         }
         return Self {
             binary,
+            extends: self.extends,
+            reset: self.reset,
+            tweak_defaults: self.tweak_defaults,
             buck_mode: self.buck_mode,
             do_not_ignore_errors_in: self
                 .do_not_ignore_errors_in
@@ -465,11 +622,533 @@ impl PartialConfiguration {
     }
 }
 
+/// The configuration keys `from_string` recognizes, used to power "did you
+/// mean" suggestions for unrecognized items.
+const KNOWN_FIELD_NAMES: &[&str] = &[
+    "binary",
+    "buck_mode",
+    "critical_files",
+    "do_not_ignore_errors_in",
+    "dot_pyre_directory",
+    "exclude",
+    "extends",
+    "extensions",
+    "ide_features",
+    "ignore_all_errors",
+    "isolation_prefix",
+    "logger",
+    "oncall",
+    "python_version",
+    "pysa_version",
+    "reset",
+    "search_path",
+    "shared_memory",
+    "site_package_search_strategy",
+    "site_roots",
+    "source_directories",
+    "strict",
+    "taint_models_path",
+    "targets",
+    "tweak_defaults",
+    "typeshed",
+    "unwatched_dependency",
+    "use_buck2",
+    "version",
+    "workers",
+];
+
+/// Classic dynamic-programming Levenshtein edit distance between `a` and `b`:
+/// cell `[i][j]` is the minimum of a deletion, an insertion, and a substitution
+/// (cost 0 when the characters match, else 1).
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut matrix = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        matrix[i][0] = i;
+    }
+    for j in 0..=b.len() {
+        matrix[0][j] = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            matrix[i][j] = (matrix[i - 1][j] + 1)
+                .min(matrix[i][j - 1] + 1)
+                .min(matrix[i - 1][j - 1] + cost);
+        }
+    }
+    matrix[a.len()][b.len()]
+}
+
+/// Return the closest known configuration field to `key`, but only when the
+/// edit distance is small relative to the key length (`<= max(2, key.len()/3)`)
+/// so unrelated keys don't get nonsense suggestions.
+fn closest_known_field(key: &str) -> Option<&'static str> {
+    let threshold = std::cmp::max(2, key.len() / 3);
+    KNOWN_FIELD_NAMES
+        .iter()
+        .map(|candidate| (*candidate, levenshtein(key, candidate)))
+        .min_by_key(|(_, distance)| *distance)
+        .filter(|(_, distance)| *distance <= threshold)
+        .map(|(candidate, _)| candidate)
+}
+
+/// For a scalar/optional field, the overwriting layer wins when it supplies a
+/// value; otherwise the base value is kept.
+fn merge_option<T>(base: Option<T>, overwrite: Option<T>) -> Option<T> {
+    overwrite.or(base)
+}
+
+/// For an accumulating list field, the base entries come first and the
+/// overwriting layer's entries are appended after them.
+fn merge_vec<T>(mut base: Vec<T>, mut overwrite: Vec<T>) -> Vec<T> {
+    base.append(&mut overwrite);
+    base
+}
+
+/// Same as [`merge_vec`] but for optional lists: concatenate when both layers
+/// contribute, otherwise fall back to whichever layer is present.
+fn merge_option_vec<T>(base: Option<Vec<T>>, overwrite: Option<Vec<T>>) -> Option<Vec<T>> {
+    match (base, overwrite) {
+        (Some(base), Some(overwrite)) => Some(merge_vec(base, overwrite)),
+        (base, overwrite) => overwrite.or(base),
+    }
+}
+
+/// Same as [`merge_vec`] but removes duplicate entries after concatenating,
+/// keeping each entry's first (i.e. furthest-ancestor) occurrence so a child
+/// that repeats a parent's path doesn't create a second copy.
+fn merge_vec_dedup<T: Eq + std::hash::Hash + Clone>(base: Vec<T>, overwrite: Vec<T>) -> Vec<T> {
+    let mut seen = HashSet::new();
+    merge_vec(base, overwrite)
+        .into_iter()
+        .filter(|item| seen.insert(item.clone()))
+        .collect()
+}
+
+/// [`merge_vec_dedup`] for optional lists, following [`merge_option_vec`]'s
+/// fallback behavior when only one layer contributes.
+fn merge_option_vec_dedup<T: Eq + std::hash::Hash + Clone>(
+    base: Option<Vec<T>>,
+    overwrite: Option<Vec<T>>,
+) -> Option<Vec<T>> {
+    match (base, overwrite) {
+        (Some(base), Some(overwrite)) => Some(merge_vec_dedup(base, overwrite)),
+        (base, overwrite) => overwrite.or(base),
+    }
+}
+
+/// Clear an accumulating list field so a `reset` key in a closer layer can
+/// drop entries inherited from the base.
+fn clear_partial_field(config: &mut PartialConfiguration, field: &str) {
+    match field {
+        "do_not_ignore_errors_in" => config.do_not_ignore_errors_in.clear(),
+        "excludes" | "exclude" => config.excludes.clear(),
+        "extensions" => config.extensions.clear(),
+        "ignore_all_errors" => config.ignore_all_errors.clear(),
+        "other_critical_files" | "critical_files" => config.other_critical_files.clear(),
+        "search_path" => config.search_path.clear(),
+        "taint_models_path" => config.taint_models_path.clear(),
+        "site_roots" => config.site_roots = None,
+        "source_directories" => config.source_directories = None,
+        "targets" => config.targets = None,
+        _ => println!("WARNING: cannot reset unknown or non-list field `{}`", field),
+    }
+}
+
 fn merge_partial_configurations(
-    base: PartialConfiguration,
+    mut base: PartialConfiguration,
     overwrite: PartialConfiguration,
 ) -> PartialConfiguration {
-    PartialConfiguration::merge(base, overwrite)
+    // Honor the overwriting layer's reset directives before merging, so that a
+    // local config can clear list fields the base accumulated.
+    for field in &overwrite.reset {
+        clear_partial_field(&mut base, field);
+    }
+    let reset = base.reset.union(&overwrite.reset).cloned().collect();
+    PartialConfiguration {
+        reset,
+        tweak_defaults: merge_option(base.tweak_defaults, overwrite.tweak_defaults),
+        binary: merge_option(base.binary, overwrite.binary),
+        buck_mode: merge_option(base.buck_mode, overwrite.buck_mode),
+        extends: merge_vec(base.extends, overwrite.extends),
+        do_not_ignore_errors_in: merge_vec_dedup(
+            base.do_not_ignore_errors_in,
+            overwrite.do_not_ignore_errors_in,
+        ),
+        dot_pyre_directory: merge_option(base.dot_pyre_directory, overwrite.dot_pyre_directory),
+        excludes: merge_vec_dedup(base.excludes, overwrite.excludes),
+        extensions: merge_vec_dedup(base.extensions, overwrite.extensions),
+        ide_features: merge_option(base.ide_features, overwrite.ide_features),
+        ignore_all_errors: merge_vec_dedup(base.ignore_all_errors, overwrite.ignore_all_errors),
+        isolation_prefix: merge_option(base.isolation_prefix, overwrite.isolation_prefix),
+        logger: merge_option(base.logger, overwrite.logger),
+        number_of_workers: merge_option(base.number_of_workers, overwrite.number_of_workers),
+        oncall: merge_option(base.oncall, overwrite.oncall),
+        other_critical_files: merge_vec_dedup(
+            base.other_critical_files,
+            overwrite.other_critical_files,
+        ),
+        pysa_version_hash: merge_option(base.pysa_version_hash, overwrite.pysa_version_hash),
+        python_version: merge_option(base.python_version, overwrite.python_version),
+        search_path: merge_vec_dedup(base.search_path, overwrite.search_path),
+        shared_memory: SharedMemory::merge(base.shared_memory, overwrite.shared_memory),
+        site_package_search_strategy: merge_option(
+            base.site_package_search_strategy,
+            overwrite.site_package_search_strategy,
+        ),
+        site_roots: merge_option_vec_dedup(base.site_roots, overwrite.site_roots),
+        source_directories: merge_option_vec_dedup(
+            base.source_directories,
+            overwrite.source_directories,
+        ),
+        strict: merge_option(base.strict, overwrite.strict),
+        taint_models_path: merge_vec_dedup(base.taint_models_path, overwrite.taint_models_path),
+        targets: merge_option_vec_dedup(base.targets, overwrite.targets),
+        typeshed: merge_option(base.typeshed, overwrite.typeshed),
+        unwatched_dependency: merge_option(
+            base.unwatched_dependency,
+            overwrite.unwatched_dependency,
+        ),
+        use_buck2: merge_option(base.use_buck2, overwrite.use_buck2),
+        version_hash: merge_option(base.version_hash, overwrite.version_hash),
+    }
+}
+
+/// Where an effective configuration value came from: the file that supplied it,
+/// a human-readable layer label ("global"/"local"/"cli"/an included path), and
+/// the labels of earlier layers it shadowed.
+#[derive(Clone)]
+pub struct ConfigOrigin {
+    pub path: String,
+    pub layer: String,
+    pub shadowed: Vec<String>,
+}
+
+/// One configuration layer together with the label and path it should be
+/// attributed to when tracking provenance.
+pub struct ConfigLayer {
+    pub label: String,
+    pub path: String,
+    pub partial: PartialConfiguration,
+}
+
+impl PartialConfiguration {
+    /// The names of the fields this layer actually supplies a value for, so
+    /// provenance tracking can tell which layer is responsible for each
+    /// effective setting.
+    fn set_fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        let mut push_if = |present: bool, name: &'static str| {
+            if present {
+                fields.push(name);
+            }
+        };
+        push_if(self.binary.is_some(), "binary");
+        push_if(self.buck_mode.is_some(), "buck_mode");
+        push_if(!self.extends.is_empty(), "extends");
+        push_if(!self.do_not_ignore_errors_in.is_empty(), "do_not_ignore_errors_in");
+        push_if(self.dot_pyre_directory.is_some(), "dot_pyre_directory");
+        push_if(!self.excludes.is_empty(), "excludes");
+        push_if(!self.extensions.is_empty(), "extensions");
+        push_if(self.ide_features.is_some(), "ide_features");
+        push_if(!self.ignore_all_errors.is_empty(), "ignore_all_errors");
+        push_if(self.isolation_prefix.is_some(), "isolation_prefix");
+        push_if(self.logger.is_some(), "logger");
+        push_if(self.number_of_workers.is_some(), "number_of_workers");
+        push_if(self.oncall.is_some(), "oncall");
+        push_if(!self.other_critical_files.is_empty(), "other_critical_files");
+        push_if(self.pysa_version_hash.is_some(), "pysa_version_hash");
+        push_if(self.python_version.is_some(), "python_version");
+        push_if(!self.search_path.is_empty(), "search_path");
+        push_if(self.shared_memory.is_set(), "shared_memory");
+        push_if(self.site_package_search_strategy.is_some(), "site_package_search_strategy");
+        push_if(self.site_roots.is_some(), "site_roots");
+        push_if(self.source_directories.is_some(), "source_directories");
+        push_if(self.strict.is_some(), "strict");
+        push_if(!self.taint_models_path.is_empty(), "taint_models_path");
+        push_if(self.targets.is_some(), "targets");
+        push_if(self.typeshed.is_some(), "typeshed");
+        push_if(self.unwatched_dependency.is_some(), "unwatched_dependency");
+        push_if(self.use_buck2.is_some(), "use_buck2");
+        push_if(self.version_hash.is_some(), "version_hash");
+        push_if(self.tweak_defaults.is_some(), "tweak_defaults");
+        fields
+    }
+}
+
+/// Attribute `field` to the layer at `path`/`label`, recording whichever prior
+/// layer it shadows (if any).
+fn record_origin(origins: &mut HashMap<String, ConfigOrigin>, field: &str, path: &str, label: &str) {
+    let shadowed = match origins.get(field) {
+        Some(existing) => {
+            let mut shadowed = existing.shadowed.clone();
+            shadowed.push(existing.layer.clone());
+            shadowed
+        }
+        None => Vec::new(),
+    };
+    origins.insert(
+        field.to_string(),
+        ConfigOrigin {
+            path: path.to_string(),
+            layer: label.to_string(),
+            shadowed,
+        },
+    );
+}
+
+/// Fold `layers` in precedence order (earliest first, latest wins) into a single
+/// `PartialConfiguration`, recording for every field which layer supplied the
+/// effective value and which earlier layers it shadowed. This preserves the
+/// provenance that [`merge_partial_configurations`] would otherwise discard.
+fn merge_layers_with_provenance(
+    layers: Vec<ConfigLayer>,
+) -> (PartialConfiguration, HashMap<String, ConfigOrigin>) {
+    let mut merged = PartialConfiguration::default();
+    let mut origins: HashMap<String, ConfigOrigin> = HashMap::new();
+    for layer in layers {
+        let set_fields = layer.partial.set_fields();
+        for field in &set_fields {
+            record_origin(&mut origins, field, &layer.path, &layer.label);
+        }
+        // A field this layer resets (via its `"reset": [...]` key)
+        // without re-setting it is cleared back to empty by
+        // `merge_partial_configurations` below, so attribute it to this layer
+        // too -- otherwise `dump_config` keeps reporting the stale,
+        // pre-reset source for a field that no longer has that value.
+        for field in &layer.partial.reset {
+            if !set_fields.contains(&field.as_str()) {
+                record_origin(&mut origins, field, &layer.path, &layer.label);
+            }
+        }
+        merged = merge_partial_configurations(merged, layer.partial);
+    }
+    (merged, origins)
+}
+
+/// Render the provenance recorded by [`merge_layers_with_provenance`] the way
+/// `pyre dump-config` presents it: one line per effective setting naming the
+/// supplying layer and file, plus the layers it shadowed.
+pub fn dump_config(origins: &HashMap<String, ConfigOrigin>) {
+    let mut fields = origins.keys().cloned().collect::<Vec<_>>();
+    fields.sort();
+    for field in fields {
+        let origin = &origins[&field];
+        if origin.shadowed.is_empty() {
+            println!("{} <- {} ({})", field, origin.layer, origin.path);
+        } else {
+            println!(
+                "{} <- {} ({}), shadowing {}",
+                field,
+                origin.layer,
+                origin.path,
+                origin.shadowed.join(", ")
+            );
+        }
+    }
+}
+
+/// Load just the `unwatched_dependency` setting out of the configuration file
+/// under `configuration_root`, for callers like `pyre check` that need it to
+/// drive fingerprinting before the rest of configuration loading (command-line
+/// overrides, local configuration merging, plain mode) is wired into their
+/// path. Returns `None` on any I/O or parse error rather than failing the
+/// whole check, since fingerprinting is an optimization, not a requirement.
+pub fn unwatched_dependency_for_root(configuration_root: &str) -> Option<UnwatchedDependency> {
+    let config_file = Path::new(configuration_root)
+        .join(CONFIGURATION_FILE)
+        .into_os_string()
+        .into_string()
+        .ok()?;
+    PartialConfiguration::from_file(&config_file)
+        .ok()?
+        .unwatched_dependency
+}
+
+/// Entry point for `pyre dump-config`: discover the global and local
+/// configuration files under `base_directory`, treat every file pulled in via
+/// `extends` as its own provenance layer rather than flattening it into the
+/// including file, and print each effective setting with its source. This is
+/// distinct from [`create_configuration`], which still merges layers with
+/// plain [`merge_partial_configurations`] and does not track provenance; a
+/// command-line layer is therefore not included here either, since
+/// `dump_config_command` has no `CommandArguments` to build one from.
+pub fn dump_config_command(base_directory: &str) -> Result<(), PyreError> {
+    let found_root = match find_global_and_local_root(base_directory)? {
+        Some(found_root) => found_root,
+        None => {
+            println!("No {} found above {}", CONFIGURATION_FILE, base_directory);
+            return Ok(());
+        }
+    };
+    let global_root = found_root.global_root;
+    let global_config = Path::new(&global_root)
+        .join(CONFIGURATION_FILE)
+        .into_os_string()
+        .into_string()
+        .expect("Invalid UTF-8");
+    let mut visited = HashSet::new();
+    let mut layers = PartialConfiguration::layers_for_file(&global_config, "global", &mut visited)?;
+    if let Some(local_root) = found_root.local_root {
+        let local_config = Path::new(&local_root)
+            .join(LOCAL_CONFIGURATION_FILE)
+            .into_os_string()
+            .into_string()
+            .expect("Invalid UTF-8");
+        let mut visited = HashSet::new();
+        layers.extend(PartialConfiguration::layers_for_file(
+            &local_config,
+            "local",
+            &mut visited,
+        )?);
+    }
+    let (_merged, origins) = merge_layers_with_provenance(layers);
+    dump_config(&origins);
+    Ok(())
+}
+
+/// Reproducible/"plain" execution mode, toggled by the `PYRE_PLAIN` environment
+/// variable, that keeps CI and other scripted invocations from silently
+/// changing behavior based on a developer's per-directory local configuration
+/// or other environment-derived overrides. `PYRE_PLAINEXCEPT` names a
+/// comma-separated list of fields (matching [`PartialConfiguration`] field
+/// names, e.g. `search_path,binary`) that should still be honored from those
+/// sources even in plain mode. Any config source that reads from the
+/// environment rather than an explicit command-line argument should consult
+/// this, the same way [`create_configuration`] consults it for the local
+/// configuration layer.
+struct PlainMode {
+    except_fields: HashSet<String>,
+}
+
+impl PlainMode {
+    /// Read plain mode from `context`'s environment rather than `std::env`
+    /// directly, so this is exercisable against a fabricated environment in
+    /// tests. Returns `None` when `PYRE_PLAIN` is unset, in which case callers
+    /// should behave exactly as they did before plain mode existed.
+    fn from_context(context: &Context) -> Option<Self> {
+        context.env_var("PYRE_PLAIN")?;
+        let except_fields = context
+            .env_var("PYRE_PLAINEXCEPT")
+            .unwrap_or_default()
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect();
+        Some(Self { except_fields })
+    }
+
+    /// Keep only the fields this instance excepts from `partial`, clearing
+    /// every other field back to its default. Used to filter an
+    /// environment-derived layer (e.g. the local configuration) down to only
+    /// the fields plain mode still allows through, without skipping the load
+    /// of the layer itself.
+    fn filter(&self, partial: PartialConfiguration) -> PartialConfiguration {
+        filter_partial_configuration_fields(partial, &self.except_fields)
+    }
+}
+
+/// Clear every field of `partial` that is not named in `except_fields`,
+/// leaving the rest at [`PartialConfiguration::default`]. Backs
+/// [`PlainMode::filter`].
+fn filter_partial_configuration_fields(
+    partial: PartialConfiguration,
+    except_fields: &HashSet<String>,
+) -> PartialConfiguration {
+    let mut filtered = PartialConfiguration::default();
+    if except_fields.contains("binary") {
+        filtered.binary = partial.binary;
+    }
+    if except_fields.contains("buck_mode") {
+        filtered.buck_mode = partial.buck_mode;
+    }
+    if except_fields.contains("extends") {
+        filtered.extends = partial.extends;
+    }
+    if except_fields.contains("reset") {
+        filtered.reset = partial.reset;
+    }
+    if except_fields.contains("tweak_defaults") {
+        filtered.tweak_defaults = partial.tweak_defaults;
+    }
+    if except_fields.contains("do_not_ignore_errors_in") {
+        filtered.do_not_ignore_errors_in = partial.do_not_ignore_errors_in;
+    }
+    if except_fields.contains("dot_pyre_directory") {
+        filtered.dot_pyre_directory = partial.dot_pyre_directory;
+    }
+    if except_fields.contains("excludes") {
+        filtered.excludes = partial.excludes;
+    }
+    if except_fields.contains("extensions") {
+        filtered.extensions = partial.extensions;
+    }
+    if except_fields.contains("ide_features") {
+        filtered.ide_features = partial.ide_features;
+    }
+    if except_fields.contains("ignore_all_errors") {
+        filtered.ignore_all_errors = partial.ignore_all_errors;
+    }
+    if except_fields.contains("isolation_prefix") {
+        filtered.isolation_prefix = partial.isolation_prefix;
+    }
+    if except_fields.contains("logger") {
+        filtered.logger = partial.logger;
+    }
+    if except_fields.contains("number_of_workers") {
+        filtered.number_of_workers = partial.number_of_workers;
+    }
+    if except_fields.contains("oncall") {
+        filtered.oncall = partial.oncall;
+    }
+    if except_fields.contains("other_critical_files") {
+        filtered.other_critical_files = partial.other_critical_files;
+    }
+    if except_fields.contains("pysa_version_hash") {
+        filtered.pysa_version_hash = partial.pysa_version_hash;
+    }
+    if except_fields.contains("python_version") {
+        filtered.python_version = partial.python_version;
+    }
+    if except_fields.contains("search_path") {
+        filtered.search_path = partial.search_path;
+    }
+    if except_fields.contains("shared_memory") {
+        filtered.shared_memory = partial.shared_memory;
+    }
+    if except_fields.contains("site_package_search_strategy") {
+        filtered.site_package_search_strategy = partial.site_package_search_strategy;
+    }
+    if except_fields.contains("site_roots") {
+        filtered.site_roots = partial.site_roots;
+    }
+    if except_fields.contains("source_directories") {
+        filtered.source_directories = partial.source_directories;
+    }
+    if except_fields.contains("strict") {
+        filtered.strict = partial.strict;
+    }
+    if except_fields.contains("taint_models_path") {
+        filtered.taint_models_path = partial.taint_models_path;
+    }
+    if except_fields.contains("targets") {
+        filtered.targets = partial.targets;
+    }
+    if except_fields.contains("typeshed") {
+        filtered.typeshed = partial.typeshed;
+    }
+    if except_fields.contains("unwatched_dependency") {
+        filtered.unwatched_dependency = partial.unwatched_dependency;
+    }
+    if except_fields.contains("use_buck2") {
+        filtered.use_buck2 = partial.use_buck2;
+    }
+    if except_fields.contains("version_hash") {
+        filtered.version_hash = partial.version_hash;
+    }
+    filtered
 }
 
 fn create_configuration(
@@ -506,11 +1185,8 @@ fn create_configuration(
         },
         None => Ok(())
     }?;
-    let cwd = std::env::current_dir()
-        .expect("Cannot get current_dir")
-        .into_os_string()
-        .into_string()
-        .expect("cannot convert");
+    let context = Context::from_real_environment().map_err(|e| e.to_string())?;
+    let cwd = path_to_string(&context.current_dir).map_err(|e| e.to_string())?;
 
     let command_argument_configuration =
         PartialConfiguration::from_command_arguments(arguments).expand_relative_paths(&cwd);
@@ -528,16 +1204,27 @@ fn create_configuration(
                 .into_string()
                 .expect("cannot convert");
             let relative_local_root = None;
-            let partial_configuration =
-                PartialConfiguration::from_file(&config_file).expand_relative_paths(&project_root);
+            let partial_configuration = PartialConfiguration::from_file(&config_file)
+                .map_err(|e| e.to_string())?
+                .expand_relative_paths(&project_root);
             let local_root = found_root.local_root;
             match local_root {
                 Some(local_root) => {
                     let relative_local_root = get_relative_local_root(project_root, local_root);
+                    let local_partial_configuration =
+                        PartialConfiguration::from_file(local_root / LOCAL_CONFIGURATION_FILE)
+                            .map_err(|e| e.to_string())?
+                            .expand_relative_paths(str(local_root));
+                    // In plain mode, the local configuration is still loaded (so
+                    // errors in it surface the same way) but only the excepted
+                    // fields are allowed to affect the result.
+                    let local_partial_configuration = match PlainMode::from_context(&context) {
+                        Some(plain_mode) => plain_mode.filter(local_partial_configuration),
+                        None => local_partial_configuration,
+                    };
                     let partial_configuration = merge_partial_configurations(
                         partial_configuration,
-                        PartialConfiguration::from_file(local_root / LOCAL_CONFIGURATION_FILE)
-                            .expand_relative_paths(str(local_root)),
+                        local_partial_configuration,
                     );
                 }
                 None => (),
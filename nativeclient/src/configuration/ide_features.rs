@@ -4,8 +4,48 @@ const DEFAULT_FIND_SYMBOLS_ENABLED: bool = false;
 const DEFAULT_FIND_ALL_REFERENCES_ENABLED: bool = false;
 
 pub struct IdeFeatures {
-    hover_enabled: Option<bool>,
-    go_to_definition_enabled: Option<bool>,
-    find_symbols_enabled: Option<bool>,
-    find_all_references_enabled: Option<bool>,
-}
\ No newline at end of file
+    pub(crate) hover_enabled: Option<bool>,
+    pub(crate) go_to_definition_enabled: Option<bool>,
+    pub(crate) find_symbols_enabled: Option<bool>,
+    pub(crate) find_all_references_enabled: Option<bool>,
+}
+
+impl IdeFeatures {
+    /// Resolve each toggle against its `DEFAULT_*_ENABLED` constant so callers
+    /// see a concrete `bool` whether or not the user set the option.
+    pub fn is_hover_enabled(&self) -> bool {
+        self.hover_enabled.unwrap_or(DEFAULT_HOVER_ENABLED)
+    }
+
+    pub fn is_go_to_definition_enabled(&self) -> bool {
+        self.go_to_definition_enabled
+            .unwrap_or(DEFAULT_GO_TO_DEFINITION_ENABLED)
+    }
+
+    pub fn is_find_symbols_enabled(&self) -> bool {
+        self.find_symbols_enabled
+            .unwrap_or(DEFAULT_FIND_SYMBOLS_ENABLED)
+    }
+
+    pub fn is_find_all_references_enabled(&self) -> bool {
+        self.find_all_references_enabled
+            .unwrap_or(DEFAULT_FIND_ALL_REFERENCES_ENABLED)
+    }
+
+    /// Merge two layers field-by-field: the overwriting layer wins for any
+    /// toggle it sets, falling back to the base layer otherwise.
+    pub(crate) fn merge(base: IdeFeatures, overwrite: IdeFeatures) -> IdeFeatures {
+        IdeFeatures {
+            hover_enabled: overwrite.hover_enabled.or(base.hover_enabled),
+            go_to_definition_enabled: overwrite
+                .go_to_definition_enabled
+                .or(base.go_to_definition_enabled),
+            find_symbols_enabled: overwrite
+                .find_symbols_enabled
+                .or(base.find_symbols_enabled),
+            find_all_references_enabled: overwrite
+                .find_all_references_enabled
+                .or(base.find_all_references_enabled),
+        }
+    }
+}
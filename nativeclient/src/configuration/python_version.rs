@@ -1,46 +1,55 @@
+use crate::error::PyreError;
 use std::fmt::{Display, Formatter};
 
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PythonVersion {
     major: i32,
     minor: i32,
     micro: i32,
 }
 
-struct InvalidPythonVersionError {
-    msg: String,
-}
-
-impl From<std::num::ParseIntError> for InvalidPythonVersionError {
-    fn from(e: std::num::ParseIntError) -> Self {
-        InvalidPythonVersionError {
-            msg: "Int parse error".to_string(),
-        }
+/// Parse a single `X`/`Y`/`Z` component, rejecting empty and negative values so
+/// that malformed versions like `3..1` or `3.-1` fail loudly instead of
+/// silently defaulting to zero. A non-numeric component surfaces the underlying
+/// `ParseIntError` as the error's `source`.
+fn parse_component(s: &str) -> Result<i32, PyreError> {
+    if s.is_empty() {
+        return Err(PyreError::Version(
+            "Version component must not be empty".to_string(),
+        ));
     }
+    let value = s.parse::<i32>()?;
+    if value < 0 {
+        return Err(PyreError::Version(format!(
+            "Version component must not be negative but got `{}`",
+            s
+        )));
+    }
+    Ok(value)
 }
 
 impl PythonVersion {
-    pub fn new(s: &str) -> Result<Self, InvalidPythonVersionError> {
+    pub fn new(s: &str) -> Result<Self, PyreError> {
         let splits = s.split(".").collect::<Vec<_>>();
         match splits.len() {
             1 => Ok(PythonVersion {
-                major: splits[0].parse::<i32>()?,
+                major: parse_component(splits[0])?,
                 minor: 0,
                 micro: 0,
             }),
             2 => Ok(PythonVersion {
-                major: splits[0].parse::<i32>()?,
-                minor: splits[1].parse::<i32>()?,
+                major: parse_component(splits[0])?,
+                minor: parse_component(splits[1])?,
                 micro: 0,
             }),
             3 => Ok(PythonVersion {
-                major: splits[0].parse::<i32>()?,
-                minor: splits[1].parse::<i32>()?,
-                micro: splits[2].parse::<i32>()?,
-            }),
-            _ => Err(InvalidPythonVersionError {
-                msg: "Version string is expected to have the form of 'X.Y.Z' but got ".to_string()
-                    + s,
+                major: parse_component(splits[0])?,
+                minor: parse_component(splits[1])?,
+                micro: parse_component(splits[2])?,
             }),
+            _ => Err(PyreError::Version(
+                "Version string is expected to have the form of 'X.Y.Z' but got ".to_string() + s,
+            )),
         }
     }
 }
@@ -50,3 +59,203 @@ impl Display for PythonVersion {
         f.write_fmt(format_args!("{}.{}.{}", self.major, self.minor, self.micro))
     }
 }
+
+/// Comparison operators understood in a PEP 440-style specifier clause.
+#[derive(Clone, Copy)]
+enum Operator {
+    Gte,
+    Lte,
+    Gt,
+    Lt,
+    Eq,
+    Ne,
+}
+
+/// A single resolved clause of a [`VersionSpecifier`]. Wildcard clauses such as
+/// `==3.11.*` are lowered into half-open ranges during parsing so matching is a
+/// straightforward comparison.
+enum Clause {
+    Compare(Operator, PythonVersion),
+    /// `[lower, upper)` — the expansion of `==X.Y.*`.
+    InRange(PythonVersion, PythonVersion),
+    /// Complement of `[lower, upper)` — the expansion of `!=X.Y.*`.
+    OutsideRange(PythonVersion, PythonVersion),
+}
+
+impl Clause {
+    fn matches(&self, v: &PythonVersion) -> bool {
+        match self {
+            Clause::Compare(operator, target) => match operator {
+                Operator::Gte => v >= target,
+                Operator::Lte => v <= target,
+                Operator::Gt => v > target,
+                Operator::Lt => v < target,
+                Operator::Eq => v == target,
+                Operator::Ne => v != target,
+            },
+            Clause::InRange(lower, upper) => v >= lower && v < upper,
+            Clause::OutsideRange(lower, upper) => !(v >= lower && v < upper),
+        }
+    }
+}
+
+/// A conjunction of PEP 440-style clauses, e.g. `">=3.8,<4.0"` or `"==3.11.*"`.
+pub struct VersionSpecifier {
+    clauses: Vec<Clause>,
+}
+
+/// Expand a wildcard version (e.g. `3.11.*`) into its half-open `[lower, upper)`
+/// bounds, incrementing the last specified component for the upper bound.
+fn expand_wildcard(
+    base: &str,
+) -> Result<(PythonVersion, PythonVersion), PyreError> {
+    let components = base
+        .split(".")
+        .map(parse_component)
+        .collect::<Result<Vec<_>, _>>()?;
+    if components.is_empty() || components.len() > 2 {
+        return Err(PyreError::Version(format!(
+            "Unsupported wildcard version `{}.*`",
+            base
+        )));
+    }
+    let lower = PythonVersion {
+        major: components[0],
+        minor: *components.get(1).unwrap_or(&0),
+        micro: 0,
+    };
+    let upper = if components.len() == 1 {
+        PythonVersion {
+            major: components[0] + 1,
+            minor: 0,
+            micro: 0,
+        }
+    } else {
+        PythonVersion {
+            major: components[0],
+            minor: components[1] + 1,
+            micro: 0,
+        }
+    };
+    Ok((lower, upper))
+}
+
+fn parse_clause(raw: &str) -> Result<Clause, PyreError> {
+    let raw = raw.trim();
+    let (operator, rest) = if let Some(rest) = raw.strip_prefix(">=") {
+        (Operator::Gte, rest)
+    } else if let Some(rest) = raw.strip_prefix("<=") {
+        (Operator::Lte, rest)
+    } else if let Some(rest) = raw.strip_prefix("==") {
+        (Operator::Eq, rest)
+    } else if let Some(rest) = raw.strip_prefix("!=") {
+        (Operator::Ne, rest)
+    } else if let Some(rest) = raw.strip_prefix(">") {
+        (Operator::Gt, rest)
+    } else if let Some(rest) = raw.strip_prefix("<") {
+        (Operator::Lt, rest)
+    } else {
+        // A bare version behaves like an exact match.
+        (Operator::Eq, raw)
+    };
+    let rest = rest.trim();
+    if let Some(base) = rest.strip_suffix(".*") {
+        let (lower, upper) = expand_wildcard(base)?;
+        return match operator {
+            Operator::Eq => Ok(Clause::InRange(lower, upper)),
+            Operator::Ne => Ok(Clause::OutsideRange(lower, upper)),
+            _ => Err(PyreError::Version(format!(
+                "Wildcard versions are only valid with `==`/`!=`: `{}`",
+                raw
+            ))),
+        };
+    }
+    Ok(Clause::Compare(operator, PythonVersion::new(rest)?))
+}
+
+impl VersionSpecifier {
+    pub fn new(s: &str) -> Result<Self, PyreError> {
+        let clauses = s
+            .split(",")
+            .filter(|clause| !clause.trim().is_empty())
+            .map(parse_clause)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(VersionSpecifier { clauses })
+    }
+
+    /// Return `true` when `v` satisfies every clause in the specifier.
+    pub fn matches(&self, v: &PythonVersion) -> bool {
+        self.clauses.iter().all(|clause| clause.matches(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> PythonVersion {
+        PythonVersion::new(s).unwrap()
+    }
+
+    #[test]
+    fn parses_partial_and_full_versions() {
+        assert_eq!(version("3").to_string(), "3.0.0");
+        assert_eq!(version("3.11").to_string(), "3.11.0");
+        assert_eq!(version("3.11.4").to_string(), "3.11.4");
+    }
+
+    #[test]
+    fn rejects_malformed_versions() {
+        assert!(PythonVersion::new("").is_err());
+        assert!(PythonVersion::new("3..1").is_err());
+        assert!(PythonVersion::new("3.-1").is_err());
+        assert!(PythonVersion::new("3.11.4.1").is_err());
+        assert!(PythonVersion::new("a.b").is_err());
+    }
+
+    #[test]
+    fn orders_by_major_then_minor_then_micro() {
+        assert!(version("3.9.0") < version("3.10.0"));
+        assert!(version("3.10.0") < version("3.10.1"));
+        assert!(version("3.10.1") == version("3.10.1"));
+    }
+
+    #[test]
+    fn specifier_matches_comparison_operators() {
+        let specifier = VersionSpecifier::new(">=3.8,<4.0").unwrap();
+        assert!(specifier.matches(&version("3.8.0")));
+        assert!(specifier.matches(&version("3.11.4")));
+        assert!(!specifier.matches(&version("3.7.9")));
+        assert!(!specifier.matches(&version("4.0.0")));
+    }
+
+    #[test]
+    fn specifier_matches_exact_and_not_equal() {
+        assert!(VersionSpecifier::new("==3.11.4")
+            .unwrap()
+            .matches(&version("3.11.4")));
+        assert!(VersionSpecifier::new("!=3.11.4")
+            .unwrap()
+            .matches(&version("3.11.5")));
+        assert!(!VersionSpecifier::new("!=3.11.4")
+            .unwrap()
+            .matches(&version("3.11.4")));
+    }
+
+    #[test]
+    fn specifier_expands_wildcards_into_half_open_ranges() {
+        let specifier = VersionSpecifier::new("==3.11.*").unwrap();
+        assert!(specifier.matches(&version("3.11.0")));
+        assert!(specifier.matches(&version("3.11.99")));
+        assert!(!specifier.matches(&version("3.12.0")));
+
+        let specifier = VersionSpecifier::new("!=3.11.*").unwrap();
+        assert!(!specifier.matches(&version("3.11.0")));
+        assert!(specifier.matches(&version("3.12.0")));
+    }
+
+    #[test]
+    fn specifier_rejects_wildcards_on_unsupported_operators() {
+        assert!(VersionSpecifier::new(">3.11.*").is_err());
+    }
+}
@@ -1,8 +1,30 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct SharedMemory {
     heap_size: Option<i32>,
     dependency_table_power: Option<i32>,
     hash_table_power: Option<i32>,
 }
+
+impl SharedMemory {
+    /// Whether this layer sets any shared-memory knob, used by provenance
+    /// tracking to decide if a layer contributed the `shared_memory` field.
+    pub(crate) fn is_set(&self) -> bool {
+        self.heap_size.is_some()
+            || self.dependency_table_power.is_some()
+            || self.hash_table_power.is_some()
+    }
+
+    /// Merge two layers field-by-field: the overwriting layer wins for any knob
+    /// it sets, falling back to the base layer otherwise.
+    pub(crate) fn merge(base: SharedMemory, overwrite: SharedMemory) -> SharedMemory {
+        SharedMemory {
+            heap_size: overwrite.heap_size.or(base.heap_size),
+            dependency_table_power: overwrite
+                .dependency_table_power
+                .or(base.dependency_table_power),
+            hash_table_power: overwrite.hash_table_power.or(base.hash_table_power),
+        }
+    }
+}
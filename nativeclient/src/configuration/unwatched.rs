@@ -1,3 +1,4 @@
+use crate::error::PyreError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,14 +7,40 @@ struct UnwatchedFiles {
     checksum_path: String,
 }
 
-struct InvalidConfigurationError {
-    msg: String,
+/// Join a dotted key path, skipping the separator at the root so top-level keys
+/// are reported bare (`files`) and nested ones fully qualified (`files.root`).
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
 }
 
 impl UnwatchedFiles {
-    fn from_json(s: &str) -> serde_json::Result<Self> {
-        serde_json::from_str(s)
+    const FIELDS: &'static [&'static str] = &["root", "checksum_path"];
+
+    pub(crate) fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub(crate) fn checksum_path(&self) -> &str {
+        &self.checksum_path
     }
+
+    /// Record every key under `value` that does not map onto a declared field,
+    /// qualified with `prefix`, so callers can flag typos instead of silently
+    /// dropping the option.
+    fn collect_unknown_keys(value: &serde_json::Value, prefix: &str, unknown: &mut Vec<String>) {
+        if let Some(object) = value.as_object() {
+            for key in object.keys() {
+                if !Self::FIELDS.contains(&key.as_str()) {
+                    unknown.push(join_key(prefix, key));
+                }
+            }
+        }
+    }
+
     fn to_json(&self) -> String {
         serde_json::to_string(self).expect("Failed to send to JSON")
     }
@@ -26,9 +53,47 @@ pub struct UnwatchedDependency {
 }
 
 impl UnwatchedDependency {
-    fn from_json(s: &str) -> serde_json::Result<Self> {
-        serde_json::from_str(s)
+    const FIELDS: &'static [&'static str] = &["change_indicator", "files"];
+
+    pub(crate) fn change_indicator(&self) -> &str {
+        &self.change_indicator
+    }
+
+    pub(crate) fn files(&self) -> &UnwatchedFiles {
+        &self.files
     }
+
+    /// Parse `s` while rejecting any key the struct (or its nested
+    /// `UnwatchedFiles`) does not declare. Unrecognized keys are reported as a
+    /// single [`PyreError::Configuration`] listing each offending dotted path
+    /// (e.g. `unwatched_dependency.files.extra_key`), so a mistyped field in a
+    /// `.pyre_configuration` surfaces instead of being quietly ignored.
+    pub(crate) fn from_json_strict(s: &str) -> Result<Self, PyreError> {
+        let value: serde_json::Value = serde_json::from_str(s)?;
+        let mut unknown = Vec::new();
+        if let Some(object) = value.as_object() {
+            for key in object.keys() {
+                if !Self::FIELDS.contains(&key.as_str()) {
+                    unknown.push(join_key("unwatched_dependency", key));
+                }
+            }
+            if let Some(files) = object.get("files") {
+                UnwatchedFiles::collect_unknown_keys(
+                    files,
+                    "unwatched_dependency.files",
+                    &mut unknown,
+                );
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(PyreError::Configuration(format!(
+                "Unrecognized configuration keys: {}",
+                unknown.join(", ")
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
     fn to_json(&self) -> String {
         serde_json::to_string(self).expect("Failed to send to JSON")
     }
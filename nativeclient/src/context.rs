@@ -0,0 +1,128 @@
+use crate::error::PyreError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Abstraction over environment-variable lookups, so code that reads them can
+/// be exercised against a fake environment in tests instead of the process's
+/// real one.
+pub trait Env {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+/// [`Env`] backed by the real process environment.
+pub struct RealEnv;
+
+impl Env for RealEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
+/// [`Env`] backed by an in-memory map, for tests.
+#[derive(Default)]
+pub struct FakeEnv {
+    vars: HashMap<String, String>,
+}
+
+impl FakeEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.vars.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl Env for FakeEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        self.vars.get(key).cloned()
+    }
+}
+
+/// Ambient state (current directory and environment) threaded explicitly
+/// through root-finding and path-expansion code instead of those functions
+/// reaching into `std::env` themselves. This is what lets that code be
+/// exercised against a fabricated directory tree and environment in tests
+/// rather than the real process's.
+pub struct Context {
+    /// The directory invocations should be resolved relative to.
+    pub current_dir: PathBuf,
+    /// The current directory as originally reported, before any further
+    /// resolution. Both constructors set it to the same path as
+    /// `current_dir`; a shell's logical `$PWD` (e.g. a path through a
+    /// symlink) is not consulted.
+    pub logical_dir: PathBuf,
+    pub env: Box<dyn Env>,
+}
+
+impl Context {
+    /// Build a `Context` from the real process current directory and
+    /// environment.
+    pub fn from_real_environment() -> Result<Self, PyreError> {
+        let logical_dir = std::env::current_dir()?;
+        Ok(Context {
+            current_dir: logical_dir.clone(),
+            logical_dir,
+            env: Box::new(RealEnv),
+        })
+    }
+
+    /// Build a `Context` over a fabricated directory tree and environment,
+    /// for tests.
+    pub fn for_test(current_dir: &Path, env: FakeEnv) -> Self {
+        Context {
+            current_dir: current_dir.to_path_buf(),
+            logical_dir: current_dir.to_path_buf(),
+            env: Box::new(env),
+        }
+    }
+
+    pub fn env_var(&self, key: &str) -> Option<String> {
+        self.env.get(key)
+    }
+}
+
+/// Convert `path` to a `String`, returning a [`PyreError`] instead of
+/// panicking when it is not valid UTF-8.
+pub fn path_to_string(path: &Path) -> Result<String, PyreError> {
+    path.to_str().map(String::from).ok_or_else(|| {
+        PyreError::Configuration(format!("`{}` is not a UTF-8 path", path.display()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_env_returns_set_variables() {
+        let env = FakeEnv::new().set("PYRE_PLAIN", "1");
+        assert_eq!(env.get("PYRE_PLAIN"), Some("1".to_string()));
+        assert_eq!(env.get("PYRE_PLAINEXCEPT"), None);
+    }
+
+    #[test]
+    fn for_test_reads_through_fake_env() {
+        let context = Context::for_test(
+            Path::new("/fake/project"),
+            FakeEnv::new().set("PYRE_PLAINEXCEPT", "search_path"),
+        );
+        assert_eq!(context.current_dir, Path::new("/fake/project"));
+        assert_eq!(context.logical_dir, Path::new("/fake/project"));
+        assert_eq!(
+            context.env_var("PYRE_PLAINEXCEPT"),
+            Some("search_path".to_string())
+        );
+        assert_eq!(context.env_var("PYRE_PLAIN"), None);
+    }
+
+    #[test]
+    fn path_to_string_converts_valid_utf8_paths() {
+        assert_eq!(
+            path_to_string(Path::new("/fake/project")).unwrap(),
+            "/fake/project"
+        );
+    }
+}
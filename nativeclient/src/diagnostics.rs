@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+/// Severity attached to a [`Diagnostic`]. Serialized as the lowercase strings
+/// `"error"`/`"warning"` that editors and CI expect to find in the JSON output.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single machine-readable diagnostic produced by `pyre check --output=json`.
+///
+/// The shape is deliberately flat and free of Rust-specific formatting so that
+/// the serialized array is a newline-free JSON document downstream tools can
+/// parse directly, one object per type error.
+#[derive(Serialize, Debug)]
+pub struct Diagnostic {
+    pub path: String,
+    pub line: i32,
+    pub column: i32,
+    pub stop_line: i32,
+    pub stop_column: i32,
+    pub severity: Severity,
+    /// Stable rule name/code identifying the kind of error.
+    pub code: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concise_description: Option<String>,
+}
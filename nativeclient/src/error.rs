@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Crate-wide error type. Each variant that wraps a lower-level failure keeps
+/// the originating error as its `source`, so `main` can print the full chain
+/// rather than a single flattened string.
+#[derive(Debug)]
+pub enum PyreError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    ParseInt(std::num::ParseIntError),
+    Configuration(String),
+    Version(String),
+    Check(String),
+}
+
+impl Display for PyreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PyreError::Io(_) => write!(f, "I/O error"),
+            PyreError::Json(_) => write!(f, "JSON error"),
+            PyreError::ParseInt(_) => write!(f, "Invalid integer"),
+            PyreError::Configuration(msg) => write!(f, "{}", msg),
+            PyreError::Version(msg) => write!(f, "{}", msg),
+            PyreError::Check(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for PyreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PyreError::Io(e) => Some(e),
+            PyreError::Json(e) => Some(e),
+            PyreError::ParseInt(e) => Some(e),
+            PyreError::Configuration(_) | PyreError::Version(_) | PyreError::Check(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PyreError {
+    fn from(e: std::io::Error) -> Self {
+        PyreError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PyreError {
+    fn from(e: serde_json::Error) -> Self {
+        PyreError::Json(e)
+    }
+}
+
+impl From<std::num::ParseIntError> for PyreError {
+    fn from(e: std::num::ParseIntError) -> Self {
+        PyreError::ParseInt(e)
+    }
+}
+
+/// Format `error` together with its `source()` chain as `a: b: c`, giving the
+/// user the full context instead of just the outermost message.
+pub fn format_error_chain(error: &PyreError) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
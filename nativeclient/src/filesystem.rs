@@ -1,20 +1,28 @@
-use std::path::Path;
+use crate::error::PyreError;
+use std::path::{Path, PathBuf};
 
-pub(crate) fn expand_relative_path<'a>(root: &'a str, path: &'a str) -> &'a str {
-    let expanded_path = Path::from(path).canonicalize().expect("Cannot canonicalize");
-    if expanded_path.is_absolute() {
-        expanded_path.as_path().to_str().expect("cannot convert to string")
-    }
-    else {
-        Path::from(path).join(expanded_path).to_str().unwrap()
-    }
+/// Expand `path` to an absolute path: unchanged if already absolute,
+/// otherwise resolved relative to `root`. Returns a [`PyreError`] instead of
+/// panicking when the result is not valid UTF-8, so a malformed configuration
+/// path surfaces as an error rather than crashing the process.
+pub(crate) fn expand_relative_path(root: &str, path: &str) -> Result<String, PyreError> {
+    let candidate = Path::new(path);
+    let expanded: PathBuf = if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        Path::new(root).join(candidate)
+    };
+    expanded
+        .into_os_string()
+        .into_string()
+        .map_err(|_| PyreError::Configuration(format!("`{}` is not a UTF-8 path", path)))
 }
 
-
-pub(crate) fn expand_global_root<'a>(path: &'a str, global_root: &'a str) -> &'a str {
-    if path.startswith("//") {
-        expand_relative_path(global_root, &path[2..])
-    } else {
-        path
+/// Expand a buck-style `//`-prefixed `path` against `global_root`; paths
+/// without that prefix are returned unchanged.
+pub(crate) fn expand_global_root(path: &str, global_root: &str) -> Result<String, PyreError> {
+    match path.strip_prefix("//") {
+        Some(rest) => expand_relative_path(global_root, rest),
+        None => Ok(path.to_string()),
     }
-}
\ No newline at end of file
+}
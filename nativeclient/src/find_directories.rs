@@ -1,3 +1,5 @@
+use crate::context::path_to_string;
+use crate::error::PyreError;
 use std::path::Path;
 
 pub const CONFIGURATION_FILE: &'static str = ".pyre_configuration";
@@ -9,27 +11,25 @@ fn _find_parent_directory_containing(
     target: &str,
     predicate: fn(&str) -> bool,
     stop_search_after: Option<i32>,
-) -> Option<String> {
+) -> Result<Option<String>, PyreError> {
     for (i, candidate_directory) in Path::new(base).ancestors().enumerate() {
-        let candidate_path = Path::new(candidate_directory).join(Path::new(target));
+        let candidate_path = candidate_directory.join(Path::new(target));
         // We might not have sufficient permission to read the file/directory.
         // In that case, pretend the file doesn't exist.
         // TODO: Check permissions.
-        if predicate(candidate_path.as_path().to_str().expect("Not a UTF-8 path")) {
-            return Some(String::from(
-                candidate_directory.to_str().expect("Not a UTF-8 path"),
-            ));
+        if predicate(&path_to_string(&candidate_path)?) {
+            return Ok(Some(path_to_string(candidate_directory)?));
         }
         match stop_search_after {
             None => {}
             Some(stop) => {
                 if i >= stop as usize {
-                    return None;
+                    return Ok(None);
                 }
             }
         }
     }
-    return None;
+    Ok(None)
 }
 
 /// Walk directories upwards from `base`, until the root directory is
@@ -42,16 +42,16 @@ fn find_parent_directory_containing_file(
     base: &str,
     target: &str,
     stop_search_after: Option<i32>,
-) -> Option<String> {
-    return _find_parent_directory_containing(
+) -> Result<Option<String>, PyreError> {
+    _find_parent_directory_containing(
         base,
         target,
         |p: &str| Path::new(p).is_file(),
         stop_search_after,
-    );
+    )
 }
 
-struct FoundRoot {
+pub struct FoundRoot {
     pub global_root: String,
     pub local_root: Option<String>,
 }
@@ -62,39 +62,47 @@ struct FoundRoot {
 /// If a global configuration exists but no local configuration is found below it,
 /// return the path to the global configuration.
 /// If both global and local exist, return them as a pair.
-pub fn find_global_and_local_root(base: &str) -> Option<FoundRoot> {
-    let found_global_root = find_parent_directory_containing_file(base, CONFIGURATION_FILE, None)?;
+pub fn find_global_and_local_root(base: &str) -> Result<Option<FoundRoot>, PyreError> {
+    let found_global_root =
+        match find_parent_directory_containing_file(base, CONFIGURATION_FILE, None)? {
+            Some(found_global_root) => found_global_root,
+            None => return Ok(None),
+        };
 
     let found_local_root =
-        find_parent_directory_containing_file(base, LOCAL_CONFIGURATION_FILE, None);
+        find_parent_directory_containing_file(base, LOCAL_CONFIGURATION_FILE, None)?;
     match found_local_root {
-        None => Some(FoundRoot {
+        None => Ok(Some(FoundRoot {
             global_root: found_global_root,
             local_root: None,
-        }),
+        })),
         Some(found_local_root) => {
             // If the global configuration root is deeper than local configuration, ignore local.
             let ancestors = Path::new(&found_global_root)
                 .ancestors()
                 .collect::<Vec<_>>();
             if ancestors.contains(&Path::new(&found_local_root)) {
-                Some(FoundRoot {
+                Ok(Some(FoundRoot {
                     global_root: found_global_root,
                     local_root: None,
-                })
+                }))
             } else {
-                Some(FoundRoot {
+                Ok(Some(FoundRoot {
                     global_root: found_global_root,
                     local_root: Some(found_local_root),
-                })
+                }))
             }
         }
     }
 }
 
-pub fn get_relative_local_root(global_root: Path, local_root: Option<String>) -> Option<String> {
-    // except ValueError:
-    // This happens when `local_root` is not prefixed by `global_root`
-    // return None
-    local_root.map(|local_root| local_root.relative_to(global_root))
+/// Express `local_root` relative to `global_root`, or `None` if `local_root`
+/// is not nested under `global_root`.
+pub fn get_relative_local_root(global_root: &str, local_root: Option<String>) -> Option<String> {
+    local_root.and_then(|local_root| {
+        Path::new(&local_root)
+            .strip_prefix(global_root)
+            .ok()
+            .and_then(|relative| relative.to_str().map(String::from))
+    })
 }
@@ -0,0 +1,257 @@
+use crate::configuration::unwatched::UnwatchedDependency;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Recorded hash and modification time for a single tracked file.
+#[derive(Serialize, Deserialize, Clone)]
+struct FileFingerprint {
+    hash: String,
+    mtime: u64,
+}
+
+/// On-disk fingerprint store persisted between `check` runs. It remembers the
+/// last-seen digest of every file under an unwatched dependency plus the
+/// contents of each dependency's change-indicator file, so a vendored tree that
+/// did not change can be skipped entirely on the next invocation.
+#[derive(Serialize, Deserialize, Default)]
+pub struct FingerprintStore {
+    files: HashMap<String, FileFingerprint>,
+    change_indicators: HashMap<String, String>,
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn mtime_seconds(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn fingerprint_file(path: &Path) -> FileFingerprint {
+    let bytes = fs::read(path).unwrap_or_default();
+    FileFingerprint {
+        hash: hash_bytes(&bytes),
+        mtime: mtime_seconds(path),
+    }
+}
+
+/// Collect every regular file under `root`, recursing into subdirectories.
+fn walk_files(root: &Path, out: &mut Vec<String>) {
+    if root.is_file() {
+        if let Some(path) = root.to_str() {
+            out.push(path.to_string());
+        }
+        return;
+    }
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        walk_files(&entry.path(), out);
+    }
+}
+
+impl FingerprintStore {
+    /// Load the store from `path`, falling back to an empty store when the file
+    /// is missing or cannot be parsed.
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the store back to `path`.
+    pub fn save(&self, path: &str) {
+        if let Ok(contents) = serde_json::to_string(self) {
+            let _ = fs::write(path, contents);
+        }
+    }
+
+    /// Return the list of files under `dependency` that need re-analysis.
+    ///
+    /// When the dependency's change-indicator file is unchanged since the last
+    /// run and the recorded digest of its root still matches, the whole tree is
+    /// skipped and an empty list is returned. Passing `force` invalidates the
+    /// recorded state so every file is reported dirty and the store is rebuilt.
+    pub fn dirty_paths(&mut self, dependency: &UnwatchedDependency, force: bool) -> Vec<String> {
+        let files = dependency.files();
+        let root = files.root().to_string();
+        let indicator = fs::read_to_string(dependency.change_indicator()).unwrap_or_default();
+        let recorded_checksum = fs::read_to_string(files.checksum_path()).unwrap_or_default();
+
+        let mut paths = Vec::new();
+        walk_files(Path::new(&root), &mut paths);
+        paths.sort();
+
+        let fresh_checksum = hash_bytes(
+            paths
+                .iter()
+                .map(|path| fingerprint_file(Path::new(path)).hash)
+                .collect::<Vec<_>>()
+                .join("")
+                .as_bytes(),
+        );
+
+        let indicator_unchanged = self.change_indicators.get(&root) == Some(&indicator);
+        let checksum_unchanged = recorded_checksum.trim() == fresh_checksum;
+        if !force && indicator_unchanged && checksum_unchanged {
+            return Vec::new();
+        }
+
+        let mut dirty = Vec::new();
+        for path in &paths {
+            let fingerprint = fingerprint_file(Path::new(path));
+            let unchanged = !force
+                && self
+                    .files
+                    .get(path)
+                    .map(|previous| previous.hash == fingerprint.hash)
+                    .unwrap_or(false);
+            if !unchanged {
+                dirty.push(path.clone());
+            }
+            self.files.insert(path.clone(), fingerprint);
+        }
+        self.change_indicators.insert(root, indicator);
+        dirty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::unwatched::UnwatchedDependency;
+
+    /// A fresh temp directory per test, cleaned up on drop.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("pyre_fingerprint_test_{}", name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("files")).unwrap();
+            TempDir(dir)
+        }
+
+        fn file_path(&self, name: &str) -> std::path::PathBuf {
+            self.0.join("files").join(name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn make_dependency(dir: &TempDir, change_indicator: &str, checksum: &str) -> UnwatchedDependency {
+        fs::write(dir.0.join("indicator"), change_indicator).unwrap();
+        fs::write(dir.0.join("checksum"), checksum).unwrap();
+        let json = format!(
+            "{{\"change_indicator\": \"{}\", \"files\": {{\"root\": \"{}\", \"checksum_path\": \"{}\"}}}}",
+            dir.0.join("indicator").to_str().unwrap(),
+            dir.file_path("").to_str().unwrap(),
+            dir.0.join("checksum").to_str().unwrap(),
+        );
+        UnwatchedDependency::from_json_strict(&json).unwrap()
+    }
+
+    /// Recompute the checksum the way [`FingerprintStore::dirty_paths`] does,
+    /// so tests can write a `checksum_path` that matches the current file
+    /// contents.
+    fn current_checksum(root: &std::path::Path) -> String {
+        let mut paths = Vec::new();
+        walk_files(root, &mut paths);
+        paths.sort();
+        hash_bytes(
+            paths
+                .iter()
+                .map(|path| fingerprint_file(Path::new(path)).hash)
+                .collect::<Vec<_>>()
+                .join("")
+                .as_bytes(),
+        )
+    }
+
+    #[test]
+    fn first_run_reports_all_files_dirty() {
+        let dir = TempDir::new("first_run");
+        fs::write(dir.file_path("a.py"), "a").unwrap();
+        fs::write(dir.file_path("b.py"), "b").unwrap();
+        let dependency = make_dependency(&dir, "v1", "bogus");
+
+        let mut store = FingerprintStore::default();
+        let mut dirty = store.dirty_paths(&dependency, false);
+        dirty.sort();
+        assert_eq!(
+            dirty,
+            vec![
+                dir.file_path("a.py").to_str().unwrap().to_string(),
+                dir.file_path("b.py").to_str().unwrap().to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_indicator_and_checksum_skip_reanalysis() {
+        let dir = TempDir::new("unchanged");
+        fs::write(dir.file_path("a.py"), "a").unwrap();
+        let dependency = make_dependency(&dir, "v1", "bogus");
+
+        let mut store = FingerprintStore::default();
+        store.dirty_paths(&dependency, false);
+
+        // Rewrite the checksum file to match the (unchanged) file contents, the
+        // way the real dependency target would once it has finished computing
+        // it, then re-run with nothing actually different.
+        let checksum = current_checksum(&dir.file_path(""));
+        let dependency = make_dependency(&dir, "v1", &checksum);
+        let dirty = store.dirty_paths(&dependency, false);
+        assert!(dirty.is_empty());
+    }
+
+    #[test]
+    fn force_reports_all_files_even_when_unchanged() {
+        let dir = TempDir::new("force");
+        fs::write(dir.file_path("a.py"), "a").unwrap();
+        let dependency = make_dependency(&dir, "v1", "bogus");
+
+        let mut store = FingerprintStore::default();
+        store.dirty_paths(&dependency, false);
+
+        let checksum = current_checksum(&dir.file_path(""));
+        let dependency = make_dependency(&dir, "v1", &checksum);
+        let dirty = store.dirty_paths(&dependency, true);
+        assert_eq!(dirty, vec![dir.file_path("a.py").to_str().unwrap().to_string()]);
+    }
+
+    #[test]
+    fn only_the_file_that_actually_changed_is_reported_dirty() {
+        let dir = TempDir::new("changed_file");
+        fs::write(dir.file_path("a.py"), "a").unwrap();
+        fs::write(dir.file_path("b.py"), "b").unwrap();
+        let dependency = make_dependency(&dir, "v1", "bogus");
+
+        let mut store = FingerprintStore::default();
+        store.dirty_paths(&dependency, false);
+
+        fs::write(dir.file_path("a.py"), "a changed").unwrap();
+        let dependency = make_dependency(&dir, "v1", "still bogus");
+        let dirty = store.dirty_paths(&dependency, false);
+        assert_eq!(dirty, vec![dir.file_path("a.py").to_str().unwrap().to_string()]);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::error::PyreError;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Appends bytes to a named log file under a log directory (typically
+/// [`crate::find_directories::LOG_DIRECTORY`]), rotating it by size so
+/// diagnostics such as `--enable-profiling` output don't grow unbounded across
+/// runs. Construct with [`LogFile::new`] and configure rotation with
+/// [`LogFile::max_size`] / [`LogFile::max_files`] before calling
+/// [`LogFile::append`].
+pub struct LogFile {
+    directory: PathBuf,
+    name: String,
+    max_size: Option<u64>,
+    max_files: u32,
+}
+
+impl LogFile {
+    /// Build a writer for `name.log` under `directory`. Rotation is disabled
+    /// until [`LogFile::max_size`] is set.
+    pub fn new(directory: &str, name: &str) -> Self {
+        LogFile {
+            directory: PathBuf::from(directory),
+            name: name.to_string(),
+            max_size: None,
+            max_files: 1,
+        }
+    }
+
+    /// Rotate the log once it exceeds `max_size` bytes. `None` disables
+    /// rotation entirely, leaving a single ever-growing `name.log`.
+    pub fn max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Keep at most `max_files` rotated copies (`name.log.1` .. `name.log.{max_files}`)
+    /// alongside the active `name.log`. Defaults to `1`.
+    pub fn max_files(mut self, max_files: u32) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    fn path(&self) -> PathBuf {
+        self.directory.join(format!("{}.log", self.name))
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        self.directory.join(format!("{}.log.{}", self.name, index))
+    }
+
+    /// If `name.log` exists and exceeds `max_size`, rename rotated copies in
+    /// descending order (`name.log.{max_files - 1}` -> `name.log.{max_files}`,
+    /// ..., `name.log` -> `name.log.1`), dropping anything beyond `max_files`,
+    /// so the next write starts a fresh `name.log`.
+    fn rotate_if_needed(&self) -> Result<(), PyreError> {
+        let max_size = match self.max_size {
+            Some(max_size) => max_size,
+            None => return Ok(()),
+        };
+        let path = self.path();
+        let size = match fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(()),
+        };
+        if size <= max_size {
+            return Ok(());
+        }
+        if self.max_files == 0 {
+            fs::remove_file(&path)?;
+            return Ok(());
+        }
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&path, self.rotated_path(1))?;
+        Ok(())
+    }
+
+    /// Append `contents` to the log file, rotating first if needed. Creates
+    /// the log directory if it does not already exist.
+    pub fn append(&self, contents: &[u8]) -> Result<(), PyreError> {
+        fs::create_dir_all(&self.directory)?;
+        self.rotate_if_needed()?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        file.write_all(contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh temp directory per test, cleaned up on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("pyre_log_file_test_{}", name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn appends_without_rotating_below_max_size() {
+        let dir = TempDir::new("below_max_size");
+        let log = LogFile::new(dir.path(), "test").max_size(Some(100));
+        log.append(b"hello").unwrap();
+        log.append(b"world").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.0.join("test.log")).unwrap(),
+            "helloworld"
+        );
+        assert!(!dir.0.join("test.log.1").exists());
+    }
+
+    #[test]
+    fn does_not_rotate_while_at_exactly_max_size() {
+        let dir = TempDir::new("rotate_boundary");
+        let log = LogFile::new(dir.path(), "test")
+            .max_size(Some(4))
+            .max_files(2);
+        // `rotate_if_needed` checks the size of the *existing* file before a
+        // write, so a file sitting at exactly `max_size` (not yet over it)
+        // should not rotate on the next append.
+        log.append(b"1234").unwrap();
+        log.append(b"5").unwrap();
+        assert!(!dir.0.join("test.log.1").exists());
+        assert_eq!(fs::read_to_string(dir.0.join("test.log")).unwrap(), "12345");
+    }
+
+    #[test]
+    fn rotates_once_max_size_is_exceeded() {
+        let dir = TempDir::new("rotate_over_max_size");
+        let log = LogFile::new(dir.path(), "test")
+            .max_size(Some(4))
+            .max_files(2);
+        log.append(b"12345").unwrap();
+        log.append(b"6").unwrap();
+        // The prior, over-size contents are now in `test.log.1`, and the
+        // fresh write starts a new `test.log`.
+        assert_eq!(fs::read_to_string(dir.0.join("test.log.1")).unwrap(), "12345");
+        assert_eq!(fs::read_to_string(dir.0.join("test.log")).unwrap(), "6");
+    }
+
+    #[test]
+    fn evicts_rotated_copies_beyond_max_files() {
+        let dir = TempDir::new("evicts_beyond_max_files");
+        let log = LogFile::new(dir.path(), "test")
+            .max_size(Some(1))
+            .max_files(2);
+        for byte in b"abcdefg" {
+            log.append(&[*byte]).unwrap();
+        }
+        // Only the two most recent rotations are kept; "a" and "b" should
+        // have been evicted rather than growing a `test.log.3`.
+        assert_eq!(fs::read_to_string(dir.0.join("test.log")).unwrap(), "g");
+        assert_eq!(fs::read_to_string(dir.0.join("test.log.1")).unwrap(), "ef");
+        assert_eq!(fs::read_to_string(dir.0.join("test.log.2")).unwrap(), "cd");
+        assert!(!dir.0.join("test.log.3").exists());
+    }
+}
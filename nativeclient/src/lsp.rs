@@ -0,0 +1,120 @@
+use crate::configuration::ide_features::IdeFeatures;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Read, Write};
+
+/// The subset of LSP `ServerCapabilities` this client advertises. Each provider
+/// is gated by the corresponding resolved [`IdeFeatures`] flag so a feature the
+/// user disabled is never announced to the editor during `initialize`.
+struct ServerCapabilities {
+    hover_provider: bool,
+    definition_provider: bool,
+    workspace_symbol_provider: bool,
+    references_provider: bool,
+}
+
+impl ServerCapabilities {
+    fn from_ide_features(features: &IdeFeatures) -> Self {
+        Self {
+            hover_provider: features.is_hover_enabled(),
+            definition_provider: features.is_go_to_definition_enabled(),
+            workspace_symbol_provider: features.is_find_symbols_enabled(),
+            references_provider: features.is_find_all_references_enabled(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            // Full-document sync is enough while the providers below are stubs.
+            "textDocumentSync": 1,
+            "hoverProvider": self.hover_provider,
+            "definitionProvider": self.definition_provider,
+            "workspaceSymbolProvider": self.workspace_symbol_provider,
+            "referencesProvider": self.references_provider,
+        })
+    }
+}
+
+/// Read a single LSP message off `reader`, honoring the `Content-Length`
+/// framing. Returns `None` on clean end-of-stream.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some(value) = trimmed.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = match content_length {
+        Some(length) => length,
+        None => return Ok(None),
+    };
+    let mut buffer = vec![0u8; length];
+    reader.read_exact(&mut buffer)?;
+    let message = serde_json::from_slice(&buffer)?;
+    Ok(Some(message))
+}
+
+/// Serialize `message` with the `Content-Length` header the protocol requires
+/// and flush it to `writer`.
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    write!(writer, "Content-Length: {}\r\n\r\n", body.len())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+fn success_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Run the Language Server Protocol loop over stdio until the editor sends
+/// `exit`. The advertised capabilities are derived from `ide_features`.
+pub fn run(ide_features: &IdeFeatures) -> io::Result<()> {
+    let capabilities = ServerCapabilities::from_ide_features(ide_features);
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    while let Some(message) = read_message(&mut reader)? {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let id = message.get("id").cloned();
+        match method {
+            "initialize" => {
+                let id = id.unwrap_or(Value::Null);
+                let result = json!({ "capabilities": capabilities.to_json() });
+                write_message(&mut writer, &success_response(id, result))?;
+            }
+            // Notifications we acknowledge but do not yet act on.
+            "initialized" | "textDocument/didOpen" | "textDocument/didChange" => {}
+            "textDocument/hover"
+            | "textDocument/definition"
+            | "textDocument/references"
+            | "workspace/symbol" => {
+                // No analysis backend is wired up yet, so reply with the empty
+                // result each request type expects.
+                if let Some(id) = id {
+                    let result = if method == "workspace/symbol" {
+                        json!([])
+                    } else {
+                        Value::Null
+                    };
+                    write_message(&mut writer, &success_response(id, result))?;
+                }
+            }
+            "shutdown" => {
+                write_message(&mut writer, &success_response(id.unwrap_or(Value::Null), Value::Null))?;
+            }
+            "exit" => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
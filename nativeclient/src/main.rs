@@ -1,21 +1,99 @@
 mod check;
 mod command_arguments;
 mod configuration;
+mod context;
+mod diagnostics;
+mod error;
 mod filesystem;
 mod find_directories;
+mod fingerprint;
+mod log_file;
+mod lsp;
 
 use command_arguments::Commands;
+use configuration::ide_features::IdeFeatures;
+use context::{path_to_string, Context};
+use error::{format_error_chain, PyreError};
+use find_directories::{find_global_and_local_root, LOG_DIRECTORY};
 
 fn main() {
-    let args = command_arguments::get_args();
-    let res = match &args.command {
+    let args = match command_arguments::get_args() {
+        Ok(args) => args,
+        Err(e) => {
+            println!("{}", format_error_chain(&e));
+            return;
+        }
+    };
+    let res: Result<&str, PyreError> = match &args.command {
         Commands::Analysis(_analysis_args) => Ok("analysis"),
-        Commands::Check(_check_args) => {
-            check::check_command("/bin/ls", "/tmp/foo.json").and_then(|()| Ok("check complete"))
+        Commands::Check(check_args) => match Context::from_real_environment() {
+            Ok(context) => match path_to_string(&context.current_dir) {
+                Ok(cwd) => {
+                    let found_root = find_global_and_local_root(&cwd).ok().flatten();
+                    let configuration_root = found_root
+                        .as_ref()
+                        .map(|found_root| {
+                            found_root
+                                .local_root
+                                .clone()
+                                .unwrap_or_else(|| found_root.global_root.clone())
+                        })
+                        .unwrap_or_else(|| cwd.clone());
+                    // `unwatched_dependency` is read from the global
+                    // `.pyre_configuration` under `global_root` (and the files
+                    // it extends); a value set in a local configuration is not
+                    // consulted here, even when `configuration_root` (used for
+                    // relativizing error paths) is the local root.
+                    let unwatched_dependency = found_root.as_ref().and_then(|found_root| {
+                        configuration::configuration::unwatched_dependency_for_root(
+                            &found_root.global_root,
+                        )
+                    });
+                    let output = check_args.output.as_deref().unwrap_or(&args.output);
+                    check::check_command(
+                        "/bin/ls",
+                        if check_args.args_from_stdin {
+                            "-"
+                        } else {
+                            "/tmp/foo.json"
+                        },
+                        output,
+                        args.dot_pyre_directory.as_deref().unwrap_or(LOG_DIRECTORY),
+                        args.enable_profiling,
+                        args.enable_memory_profiling,
+                        &configuration_root,
+                        &cwd,
+                        unwatched_dependency.as_ref(),
+                        check_args.force,
+                    )
+                    .and_then(|()| Ok("check complete"))
+                }
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        },
+        Commands::Lsp(_lsp_args) => {
+            let ide_features = IdeFeatures {
+                hover_enabled: args.enable_hover,
+                go_to_definition_enabled: args.enable_go_to_definition,
+                find_symbols_enabled: args.enable_find_symbols,
+                find_all_references_enabled: args.enable_find_all_references,
+            };
+            lsp::run(&ide_features)
+                .map(|()| "lsp exited")
+                .map_err(PyreError::from)
         }
+        Commands::DumpConfig(_dump_config_args) => match Context::from_real_environment() {
+            Ok(context) => match path_to_string(&context.logical_dir) {
+                Ok(cwd) => configuration::configuration::dump_config_command(&cwd)
+                    .map(|()| "dump-config complete"),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        },
     };
     match res {
         Ok(s) => println!("No errors: {}", s),
-        Err(e) => println!("{}", e.msg),
+        Err(e) => println!("{}", format_error_chain(&e)),
     }
 }